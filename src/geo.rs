@@ -1,3 +1,4 @@
+use rstar::{PointDistance, RTree, RTreeObject, AABB};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -36,8 +37,48 @@ pub struct Country {
     pub name: String,
     /// Each polygon is a list of rings; ring 0 = outer, rest = holes
     pub polygons: Vec<Vec<Vec<[f64; 2]>>>,
-    pub bbox: (f64, f64, f64, f64), // (min_lon, min_lat, max_lon, max_lat)
-    pub label_pos: (f64, f64),      // (lon, lat) — centroid of largest polygon
+    /// One bbox per entry in `polygons`, (min_lon, min_lat, max_lon, max_lat).
+    /// A country whose outer ring crossed the ±180° antimeridian (Russia, Fiji,
+    /// Alaska, the Chathams) was split into two polygons in `load_countries`,
+    /// so it has two bboxes here instead of one globe-spanning one.
+    pub bboxes: Vec<(f64, f64, f64, f64)>,
+    /// The (west, east) bboxes of the antimeridian-split halves, if this
+    /// country's outer ring actually crossed the ±180° seam (Russia, Fiji,
+    /// Alaska, the Chathams). `None` for ordinary multi-polygon countries
+    /// (Indonesia, Japan, the UK, ...), which have several `bboxes` from
+    /// offshore islands without ever touching the seam — and NOT simply
+    /// `bboxes[0]`/`bboxes[last]`, since an exclave or island elsewhere in
+    /// `polygons` could sit before or after the split halves in that list.
+    pub seam_bboxes: Option<((f64, f64, f64, f64), (f64, f64, f64, f64))>,
+    pub label_pos: (f64, f64), // (lon, lat) — pole of inaccessibility of largest polygon
+}
+
+/// An R-tree leaf: a bounding box tagged with its index into some parallel
+/// `Vec` (countries, timezones, ...).
+struct BboxEnvelope {
+    idx: usize,
+    envelope: AABB<[f64; 2]>,
+}
+
+impl RTreeObject for BboxEnvelope {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+impl PointDistance for BboxEnvelope {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        self.envelope.distance_2(point)
+    }
+}
+
+/// Countries plus a spatial index over their bounding boxes, built once by
+/// `load_countries` so lookups don't have to linearly scan every country.
+pub struct CountryIndex {
+    pub countries: Vec<Country>,
+    tree: RTree<BboxEnvelope>,
 }
 
 /// Signed area of a ring (positive = CCW).
@@ -76,128 +117,279 @@ fn point_in_ring(lon: f64, lat: f64, ring: &[[f64; 2]]) -> bool {
     inside
 }
 
-/// Find horizontal interior spans at a given latitude.
-/// Returns sorted pairs of (enter_lon, exit_lon).
-fn horizontal_spans(lat: f64, ring: &[[f64; 2]]) -> Vec<(f64, f64)> {
+/// Does any edge of this ring jump more than 180° in longitude, i.e. does the
+/// ring cross the ±180° antimeridian?
+fn ring_crosses_antimeridian(ring: &[[f64; 2]]) -> bool {
     let n = ring.len();
-    if n < 3 {
-        return Vec::new();
+    (0..n).any(|i| (ring[(i + 1) % n][0] - ring[i][0]).abs() > 180.0)
+}
+
+/// Latitude at which an edge that jumps the antimeridian actually crosses it,
+/// found by unrolling the far endpoint onto the near endpoint's longitude axis
+/// and interpolating to the seam.
+fn antimeridian_crossing_lat(lon0: f64, lat0: f64, lon1: f64, lat1: f64) -> f64 {
+    let unrolled1 = if lon1 > lon0 { lon1 - 360.0 } else { lon1 + 360.0 };
+    let seam = if lon0 >= 0.0 { 180.0 } else { -180.0 };
+    let t = (seam - lon0) / (unrolled1 - lon0);
+    lat0 + t * (lat1 - lat0)
+}
+
+/// Split a ring that crosses the ±180° antimeridian into a west half (near
+/// -180°) and an east half (near +180°), inserting the interpolated seam
+/// vertices so each half stays on one side. Rings that don't cross are
+/// returned unchanged.
+fn split_ring_at_antimeridian(ring: &[[f64; 2]]) -> Vec<Vec<[f64; 2]>> {
+    let n = ring.len();
+    if n < 3 || !ring_crosses_antimeridian(ring) {
+        return vec![ring.to_vec()];
     }
-    let mut crossings = Vec::new();
-    let mut j = n - 1;
+
+    let mut west = Vec::new();
+    let mut east = Vec::new();
+    let mut on_east = ring[0][0] >= 0.0;
+
     for i in 0..n {
-        let yi = ring[i][1];
-        let yj = ring[j][1];
-        if (yi > lat) != (yj > lat) {
-            let xi = ring[i][0];
-            let xj = ring[j][0];
-            crossings.push((xj - xi) * (lat - yi) / (yj - yi) + xi);
+        let j = (i + 1) % n;
+        let (lon0, lat0) = (ring[i][0], ring[i][1]);
+        let (lon1, lat1) = (ring[j][0], ring[j][1]);
+
+        if on_east {
+            east.push([lon0, lat0]);
+        } else {
+            west.push([lon0, lat0]);
+        }
+
+        if (lon1 - lon0).abs() > 180.0 {
+            let crossing_lat = antimeridian_crossing_lat(lon0, lat0, lon1, lat1);
+            east.push([180.0, crossing_lat]);
+            west.push([-180.0, crossing_lat]);
+            on_east = !on_east;
         }
-        j = i;
     }
-    crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    crossings.chunks_exact(2).map(|p| (p[0], p[1])).collect()
+
+    vec![west, east].into_iter().filter(|r| r.len() >= 3).collect()
 }
 
-/// Find vertical interior spans at a given longitude.
-/// Returns sorted pairs of (enter_lat, exit_lat).
-fn vertical_spans(lon: f64, ring: &[[f64; 2]]) -> Vec<(f64, f64)> {
-    let n = ring.len();
-    if n < 3 {
-        return Vec::new();
+/// Split any polygon whose outer ring crosses the ±180° antimeridian into two
+/// polygons, one per side of the seam, so no polygon's bbox spans the globe.
+/// Holes are kept with whichever half their mean longitude falls into.
+///
+/// Also returns the (west, east) indices into the result of the split halves,
+/// if a split happened. A country's other polygons (exclaves, offshore
+/// islands) are left untouched and keep their original relative order, so
+/// this index pair — not position 0 / `len() - 1` of the full result — is the
+/// only reliable way to find the halves back out.
+pub fn split_polygons_at_antimeridian(
+    polygons: Vec<Vec<Vec<[f64; 2]>>>,
+) -> (Vec<Vec<Vec<[f64; 2]>>>, Option<(usize, usize)>) {
+    let mut out = Vec::with_capacity(polygons.len());
+    let mut seam_indices = None;
+    for poly in polygons {
+        if poly.is_empty() || !ring_crosses_antimeridian(&poly[0]) {
+            out.push(poly);
+            continue;
+        }
+
+        let west_idx = out.len();
+        let mut halves: Vec<Vec<Vec<[f64; 2]>>> =
+            split_ring_at_antimeridian(&poly[0]).into_iter().map(|r| vec![r]).collect();
+        for hole in &poly[1..] {
+            let mean_lon = hole.iter().map(|c| c[0]).sum::<f64>() / hole.len() as f64;
+            let target = if mean_lon >= 0.0 { halves.len() - 1 } else { 0 };
+            halves[target].push(hole.clone());
+        }
+        let split_in_two = halves.len() == 2;
+        out.extend(halves);
+        if split_in_two {
+            seam_indices = Some((west_idx, west_idx + 1));
+        }
+    }
+    (out, seam_indices)
+}
+
+/// Bounding box of a polygon (all its rings), as (min_lon, min_lat, max_lon, max_lat).
+pub fn polygon_bbox(poly: &[Vec<[f64; 2]>]) -> (f64, f64, f64, f64) {
+    let mut min_lon = f64::MAX;
+    let mut min_lat = f64::MAX;
+    let mut max_lon = f64::MIN;
+    let mut max_lat = f64::MIN;
+    for ring in poly {
+        for coord in ring {
+            let (lon, lat) = (coord[0], coord[1]);
+            if lon < min_lon {
+                min_lon = lon;
+            }
+            if lon > max_lon {
+                max_lon = lon;
+            }
+            if lat < min_lat {
+                min_lat = lat;
+            }
+            if lat > max_lat {
+                max_lat = lat;
+            }
+        }
+    }
+    (min_lon, min_lat, max_lon, max_lat)
+}
+
+/// Check if a point falls inside any of a set of (polygon, bbox) pairs, e.g. a
+/// country's or timezone's polygons. Each polygon is rejected against its own
+/// bbox first, so a seam-split region (two polygons, two bboxes) doesn't need
+/// a single globe-spanning bbox to be correct.
+fn point_in_rings_set(lon: f64, lat: f64, polygons: &[Vec<Vec<[f64; 2]>>], bboxes: &[(f64, f64, f64, f64)]) -> bool {
+    polygons.iter().zip(bboxes).any(|(poly, &(min_lon, min_lat, max_lon, max_lat))| {
+        if lon < min_lon || lon > max_lon || lat < min_lat || lat > max_lat {
+            return false;
+        }
+        point_in_polygon(lon, lat, poly)
+    })
+}
+
+/// Shortest distance from a point to a line segment.
+fn point_segment_distance(px: f64, py: f64, ax: f64, ay: f64, bx: f64, by: f64) -> f64 {
+    let dx = bx - ax;
+    let dy = by - ay;
+    if dx == 0.0 && dy == 0.0 {
+        return ((px - ax).powi(2) + (py - ay).powi(2)).sqrt();
     }
-    let mut crossings = Vec::new();
+    let t = (((px - ax) * dx + (py - ay) * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0);
+    let cx = ax + t * dx;
+    let cy = ay + t * dy;
+    ((px - cx).powi(2) + (py - cy).powi(2)).sqrt()
+}
+
+/// Signed distance from a point to the ring boundary: positive when the point
+/// is inside the ring, negative when outside.
+fn ring_boundary_distance(lon: f64, lat: f64, ring: &[[f64; 2]]) -> f64 {
+    let n = ring.len();
+    let mut min_dist = f64::MAX;
     let mut j = n - 1;
     for i in 0..n {
-        let xi = ring[i][0];
-        let xj = ring[j][0];
-        if (xi > lon) != (xj > lon) {
-            let yi = ring[i][1];
-            let yj = ring[j][1];
-            crossings.push((yj - yi) * (lon - xi) / (xj - xi) + yi);
+        let d = point_segment_distance(lon, lat, ring[j][0], ring[j][1], ring[i][0], ring[i][1]);
+        if d < min_dist {
+            min_dist = d;
         }
         j = i;
     }
-    crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    crossings.chunks_exact(2).map(|p| (p[0], p[1])).collect()
+    if point_in_ring(lon, lat, ring) {
+        min_dist
+    } else {
+        -min_dist
+    }
 }
 
-/// Find a good interior label point for a ring.
-/// Scans a grid of candidate points and picks the one that maximizes
-/// min(half_width, half_height) — the "most interior" point.
+/// A square cell used by the polylabel search, keyed for the max-heap by the
+/// upper bound on the distance any point in the cell could have to the boundary.
+struct LabelCell {
+    lon: f64,
+    lat: f64,
+    h: f64,
+    d: f64,
+    max: f64,
+}
+
+impl LabelCell {
+    fn new(lon: f64, lat: f64, h: f64, ring: &[[f64; 2]]) -> Self {
+        let d = ring_boundary_distance(lon, lat, ring);
+        LabelCell {
+            lon,
+            lat,
+            h,
+            d,
+            max: d + h * std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+impl PartialEq for LabelCell {
+    fn eq(&self, other: &Self) -> bool {
+        self.max == other.max
+    }
+}
+impl Eq for LabelCell {}
+impl PartialOrd for LabelCell {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for LabelCell {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.max.partial_cmp(&other.max).unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+/// Mean of a ring's vertices — a cheap centroid, good enough as a polylabel
+/// seed or as the anchor point for a custom WKT region.
+pub fn ring_centroid(ring: &[[f64; 2]]) -> (f64, f64) {
+    let lon = ring.iter().map(|c| c[0]).sum::<f64>() / ring.len() as f64;
+    let lat = ring.iter().map(|c| c[1]).sum::<f64>() / ring.len() as f64;
+    (lon, lat)
+}
+
+/// Find the pole of inaccessibility of a ring: the interior point that
+/// maximizes distance to the boundary (Mapbox's `polylabel` algorithm). Unlike
+/// a bbox-center scan, this doesn't drift outside concave shapes like Norway
+/// or Chile.
 fn ring_label_point(ring: &[[f64; 2]]) -> (f64, f64) {
     let min_lon = ring.iter().map(|c| c[0]).fold(f64::MAX, f64::min);
     let max_lon = ring.iter().map(|c| c[0]).fold(f64::MIN, f64::max);
     let min_lat = ring.iter().map(|c| c[1]).fold(f64::MAX, f64::min);
     let max_lat = ring.iter().map(|c| c[1]).fold(f64::MIN, f64::max);
 
-    let steps = 24;
-    let mut best = ((min_lon + max_lon) / 2.0, (min_lat + max_lat) / 2.0);
-    let mut best_score = 0.0f64;
-
-    for row in 1..steps {
-        let lat = min_lat + (max_lat - min_lat) * row as f64 / steps as f64;
-        let h_spans = horizontal_spans(lat, ring);
-
-        for &(span_left, span_right) in &h_spans {
-            let mid_lon = (span_left + span_right) / 2.0;
-            let half_w = (span_right - span_left) / 2.0;
-
-            // Measure vertical extent at this longitude
-            let v_spans = vertical_spans(mid_lon, ring);
-            for &(span_bot, span_top) in &v_spans {
-                if lat >= span_bot && lat <= span_top {
-                    let half_h = ((lat - span_bot).min(span_top - lat)).min(half_w);
-                    let score = half_w.min(half_h);
-                    if score > best_score {
-                        best_score = score;
-                        best = (mid_lon, lat);
-                    }
-                    break;
-                }
-            }
+    let cell_size = (max_lon - min_lon).min(max_lat - min_lat);
+    if cell_size <= 0.0 {
+        return ((min_lon + max_lon) / 2.0, (min_lat + max_lat) / 2.0);
+    }
+    let h = cell_size / 2.0;
+
+    let mut heap = std::collections::BinaryHeap::new();
+    let mut lon = min_lon;
+    while lon < max_lon {
+        let mut lat = min_lat;
+        while lat < max_lat {
+            heap.push(LabelCell::new(lon + h, lat + h, h, ring));
+            lat += cell_size;
         }
+        lon += cell_size;
     }
 
-    best
+    let (centroid_lon, centroid_lat) = ring_centroid(ring);
+    let mut best = LabelCell::new(centroid_lon, centroid_lat, 0.0, ring);
+
+    let precision = cell_size * 0.01;
+
+    while let Some(cell) = heap.pop() {
+        if cell.d > best.d {
+            best = LabelCell::new(cell.lon, cell.lat, 0.0, ring);
+        }
+        if cell.max - best.d <= precision {
+            continue;
+        }
+        let half = cell.h / 2.0;
+        for &(dlon, dlat) in &[(-half, -half), (half, -half), (-half, half), (half, half)] {
+            heap.push(LabelCell::new(cell.lon + dlon, cell.lat + dlat, half, ring));
+        }
+    }
+
+    (best.lon, best.lat)
 }
 
-pub fn load_countries(geojson: &str) -> Vec<Country> {
+pub fn load_countries(geojson: &str) -> CountryIndex {
     let fc: FeatureCollection = serde_json::from_str(geojson).expect("Failed to parse GeoJSON");
 
-    fc.features
+    let countries: Vec<Country> = fc
+        .features
         .into_iter()
         .map(|f| {
             let polygons = match f.geometry {
                 Geometry::Polygon { coordinates } => vec![coordinates],
                 Geometry::MultiPolygon { coordinates } => coordinates,
             };
+            let (polygons, seam_idx) = split_polygons_at_antimeridian(polygons);
 
-            let mut min_lon = f64::MAX;
-            let mut min_lat = f64::MAX;
-            let mut max_lon = f64::MIN;
-            let mut max_lat = f64::MIN;
-
-            for poly in &polygons {
-                for ring in poly {
-                    for coord in ring {
-                        let lon = coord[0];
-                        let lat = coord[1];
-                        if lon < min_lon {
-                            min_lon = lon;
-                        }
-                        if lon > max_lon {
-                            max_lon = lon;
-                        }
-                        if lat < min_lat {
-                            min_lat = lat;
-                        }
-                        if lat > max_lat {
-                            max_lat = lat;
-                        }
-                    }
-                }
-            }
+            let bboxes: Vec<(f64, f64, f64, f64)> = polygons.iter().map(|poly| polygon_bbox(poly)).collect();
+            let seam_bboxes = seam_idx.map(|(w, e)| (bboxes[w], bboxes[e]));
 
             // Label inside the largest polygon
             let label_pos = polygons
@@ -210,17 +402,36 @@ pub fn load_countries(geojson: &str) -> Vec<Country> {
                         .unwrap()
                 })
                 .map(|p| ring_label_point(&p[0]))
-                .unwrap_or(((min_lon + max_lon) / 2.0, (min_lat + max_lat) / 2.0));
+                .unwrap_or_else(|| {
+                    let (min_lon, min_lat, max_lon, max_lat) = bboxes[0];
+                    ((min_lon + max_lon) / 2.0, (min_lat + max_lat) / 2.0)
+                });
 
             Country {
                 iso_a2: f.properties.iso_a2,
                 name: f.properties.name,
                 polygons,
-                bbox: (min_lon, min_lat, max_lon, max_lat),
+                bboxes,
+                seam_bboxes,
                 label_pos,
             }
         })
-        .collect()
+        .collect();
+
+    let tree = RTree::bulk_load(
+        countries
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, c)| {
+                c.bboxes.iter().map(move |&(min_lon, min_lat, max_lon, max_lat)| BboxEnvelope {
+                    idx,
+                    envelope: AABB::from_corners([min_lon, min_lat], [max_lon, max_lat]),
+                })
+            })
+            .collect(),
+    );
+
+    CountryIndex { countries, tree }
 }
 
 /// Check if a point is inside a polygon (outer ring minus holes).
@@ -234,42 +445,512 @@ fn point_in_polygon(lon: f64, lat: f64, rings: &[Vec<[f64; 2]>]) -> bool {
 
 /// Check if a point falls inside a country.
 pub fn point_in_country(lon: f64, lat: f64, country: &Country) -> bool {
-    let (min_lon, min_lat, max_lon, max_lat) = country.bbox;
-    if lon < min_lon || lon > max_lon || lat < min_lat || lat > max_lat {
+    point_in_rings_set(lon, lat, &country.polygons, &country.bboxes)
+}
+
+/// Find which country contains the given point, with a nearest-country
+/// fallback for when low-res coastlines cause a near miss.
+///
+/// Uses the R-tree built in `load_countries` to narrow the search to the
+/// handful of countries whose bounding box actually contains the point,
+/// instead of scanning every country.
+pub fn find_country(lon: f64, lat: f64, index: &CountryIndex) -> Option<usize> {
+    let point = [lon, lat];
+
+    // Exact hit: run the precise point-in-polygon test only on bbox candidates.
+    for candidate in index.tree.locate_all_at_point(&point) {
+        if point_in_country(lon, lat, &index.countries[candidate.idx]) {
+            return Some(candidate.idx);
+        }
+    }
+
+    // Near miss (e.g. a point just off a low-res coastline): snap to whichever
+    // country's bounding box is nearest, which is deterministic unlike the old
+    // fixed-offset ring search.
+    index.tree.nearest_neighbor(&point).map(|c| c.idx)
+}
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distance between two lon/lat points, in kilometers.
+fn haversine_km(lon0: f64, lat0: f64, lon1: f64, lat1: f64) -> f64 {
+    let (lat0r, lat1r) = (lat0.to_radians(), lat1.to_radians());
+    let dlat = (lat1 - lat0).to_radians();
+    let dlon = (lon1 - lon0).to_radians();
+    let a = (dlat / 2.0).sin().powi(2) + lat0r.cos() * lat1r.cos() * (dlon / 2.0).sin().powi(2);
+    EARTH_RADIUS_KM * 2.0 * a.sqrt().asin()
+}
+
+/// Distance in km from a point to the nearest edge of any ring in a country's
+/// polygons: find the closest point on each edge in lon/lat space (the same
+/// projection `point_segment_distance` uses), then measure the real-world
+/// distance to that point.
+fn country_distance_km(lon: f64, lat: f64, country: &Country) -> f64 {
+    let mut best = f64::MAX;
+    for poly in &country.polygons {
+        for ring in poly {
+            let n = ring.len();
+            if n < 2 {
+                continue;
+            }
+            let mut j = n - 1;
+            for i in 0..n {
+                let (ax, ay) = (ring[j][0], ring[j][1]);
+                let (bx, by) = (ring[i][0], ring[i][1]);
+                let (dx, dy) = (bx - ax, by - ay);
+                let t = if dx == 0.0 && dy == 0.0 {
+                    0.0
+                } else {
+                    (((lon - ax) * dx + (lat - ay) * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0)
+                };
+                let d = haversine_km(lon, lat, ax + t * dx, ay + t * dy);
+                if d < best {
+                    best = d;
+                }
+                j = i;
+            }
+        }
+    }
+    best
+}
+
+/// Find every country within `radius_km` of the query point, nearest first.
+///
+/// Coarsely prefilters with the R-tree (expanding the radius generously from
+/// km to degrees) before measuring exact haversine distance on each
+/// candidate, so this doesn't have to scan every country either. The degree
+/// conversion is scaled by `1 / cos(lat)` since a degree of longitude shrinks
+/// toward the poles — without it, a country offset mostly in longitude at
+/// high latitude (Scandinavia, northern Canada/Russia, ...) could fall
+/// outside the prefilter window and get silently dropped.
+pub fn countries_within_radius(lon: f64, lat: f64, radius_km: f64, index: &CountryIndex) -> Vec<(usize, f64)> {
+    let cos_lat = lat.to_radians().cos().abs().max(0.01);
+    let degree_radius = radius_km / (111.0 * cos_lat) + 1.0;
+    let point = [lon, lat];
+
+    let mut seen = std::collections::HashSet::new();
+    let mut hits = Vec::new();
+    for candidate in index.tree.locate_within_distance(point, degree_radius * degree_radius) {
+        if !seen.insert(candidate.idx) {
+            continue;
+        }
+        let country = &index.countries[candidate.idx];
+        let d = if point_in_country(lon, lat, country) {
+            0.0
+        } else {
+            country_distance_km(lon, lat, country)
+        };
+        if d <= radius_km {
+            hits.push((candidate.idx, d));
+        }
+    }
+    hits.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    hits
+}
+
+/// Orientation of point `c` relative to the directed line `a -> b`, used by
+/// `segments_intersect`'s standard sign-of-cross-product test.
+fn orientation(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Do segments (p1, p2) and (p3, p4) cross each other?
+fn segments_intersect(p1: [f64; 2], p2: [f64; 2], p3: [f64; 2], p4: [f64; 2]) -> bool {
+    let d1 = orientation(p3, p4, p1);
+    let d2 = orientation(p3, p4, p2);
+    let d3 = orientation(p1, p2, p3);
+    let d4 = orientation(p1, p2, p4);
+    (d1 > 0.0) != (d2 > 0.0) && (d3 > 0.0) != (d4 > 0.0)
+}
+
+/// Does this ring actually share territory with the rectangle
+/// [min_lon,max_lon] x [min_lat,max_lat], rather than just overlapping
+/// bboxes? True if either shape has a vertex inside the other, or any of
+/// their edges cross.
+fn ring_intersects_rect(ring: &[[f64; 2]], min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> bool {
+    if ring.iter().any(|&[lon, lat]| lon >= min_lon && lon <= max_lon && lat >= min_lat && lat <= max_lat) {
+        return true;
+    }
+    let corners = [[min_lon, min_lat], [max_lon, min_lat], [max_lon, max_lat], [min_lon, max_lat]];
+    if corners.iter().any(|&[lon, lat]| point_in_ring(lon, lat, ring)) {
+        return true;
+    }
+    let n = ring.len();
+    if n < 2 {
         return false;
     }
-    country
-        .polygons
+    let mut j = n - 1;
+    for i in 0..n {
+        for k in 0..4 {
+            if segments_intersect(ring[j], ring[i], corners[k], corners[(k + 1) % 4]) {
+                return true;
+            }
+        }
+        j = i;
+    }
+    false
+}
+
+/// Does any polygon in this set actually overlap the rectangle, not merely
+/// its bbox? Checked against each polygon's outer ring only — holes aren't
+/// subtracted, the same pragmatic scope as the rest of the antimeridian
+/// handling in this module.
+fn polygons_intersect_rect(polygons: &[Vec<Vec<[f64; 2]>>], min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> bool {
+    polygons.iter().any(|poly| !poly.is_empty() && ring_intersects_rect(&poly[0], min_lon, min_lat, max_lon, max_lat))
+}
+
+/// Find every country whose territory actually falls within the given
+/// lat/lon rectangle — bbox overlap is just the fast prefilter, not the
+/// answer, since a rectangle can sit inside a large country's bbox (e.g.
+/// Canada's) without touching any of its real land.
+pub fn countries_within_bbox(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64, countries: &[Country]) -> Vec<usize> {
+    countries
         .iter()
-        .any(|poly| point_in_polygon(lon, lat, poly))
+        .enumerate()
+        .filter(|(_, c)| {
+            c.bboxes.iter().any(|&(c_min_lon, c_min_lat, c_max_lon, c_max_lat)| {
+                c_min_lon <= max_lon && c_max_lon >= min_lon && c_min_lat <= max_lat && c_max_lat >= min_lat
+            }) && polygons_intersect_rect(&c.polygons, min_lon, min_lat, max_lon, max_lat)
+        })
+        .map(|(idx, _)| idx)
+        .collect()
 }
 
-/// Find which country contains the given point, with a nearest-country
-/// fallback for when low-res coastlines cause a near miss.
-pub fn find_country(lon: f64, lat: f64, countries: &[Country]) -> Option<usize> {
-    // Exact hit
-    if let Some(idx) = countries.iter().position(|c| point_in_country(lon, lat, c)) {
-        return Some(idx);
-    }
-    // Search in expanding rings up to ~1 degree
-    for &offset in &[0.25, 0.5, 1.0] {
-        for &(dlon, dlat) in &[
-            (offset, 0.0),
-            (-offset, 0.0),
-            (0.0, offset),
-            (0.0, -offset),
-            (offset, offset),
-            (offset, -offset),
-            (-offset, offset),
-            (-offset, -offset),
-        ] {
-            if let Some(idx) = countries
-                .iter()
-                .position(|c| point_in_country(lon + dlon, lat + dlat, c))
-            {
-                return Some(idx);
+#[derive(Debug, Deserialize)]
+struct TzFeatureCollection {
+    features: Vec<TzFeature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TzFeature {
+    properties: TzProperties,
+    geometry: Geometry,
+}
+
+#[derive(Debug, Deserialize)]
+struct TzProperties {
+    #[serde(rename = "tzid")]
+    tzid: String,
+}
+
+pub struct TimeZone {
+    pub name: String, // IANA timezone name, e.g. "Europe/Warsaw"
+    pub polygons: Vec<Vec<Vec<[f64; 2]>>>,
+    pub bboxes: Vec<(f64, f64, f64, f64)>,
+}
+
+/// Timezones plus a spatial index over their bounding boxes, mirroring `CountryIndex`.
+pub struct TimeZoneIndex {
+    pub zones: Vec<TimeZone>,
+    tree: RTree<BboxEnvelope>,
+}
+
+/// Load timezone boundaries the same way `load_countries` loads country
+/// boundaries: parse the GeoJSON, split any zone that crosses the
+/// antimeridian, and build an R-tree over the resulting bboxes.
+pub fn load_timezones(geojson: &str) -> TimeZoneIndex {
+    let fc: TzFeatureCollection = serde_json::from_str(geojson).expect("Failed to parse timezone GeoJSON");
+
+    let zones: Vec<TimeZone> = fc
+        .features
+        .into_iter()
+        .map(|f| {
+            let polygons = match f.geometry {
+                Geometry::Polygon { coordinates } => vec![coordinates],
+                Geometry::MultiPolygon { coordinates } => coordinates,
+            };
+            let (polygons, _) = split_polygons_at_antimeridian(polygons);
+            let bboxes = polygons.iter().map(|poly| polygon_bbox(poly)).collect();
+
+            TimeZone {
+                name: f.properties.tzid,
+                polygons,
+                bboxes,
             }
+        })
+        .collect();
+
+    let tree = RTree::bulk_load(
+        zones
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, z)| {
+                z.bboxes.iter().map(move |&(min_lon, min_lat, max_lon, max_lat)| BboxEnvelope {
+                    idx,
+                    envelope: AABB::from_corners([min_lon, min_lat], [max_lon, max_lat]),
+                })
+            })
+            .collect(),
+    );
+
+    TimeZoneIndex { zones, tree }
+}
+
+/// Find which timezone contains the given point, with the same
+/// nearest-bbox fallback as `find_country` for coastal/ocean points.
+pub fn find_timezone(lon: f64, lat: f64, index: &TimeZoneIndex) -> Option<usize> {
+    let point = [lon, lat];
+
+    for candidate in index.tree.locate_all_at_point(&point) {
+        let zone = &index.zones[candidate.idx];
+        if point_in_rings_set(lon, lat, &zone.polygons, &zone.bboxes) {
+            return Some(candidate.idx);
         }
     }
-    None
+
+    index.tree.nearest_neighbor(&point).map(|c| c.idx)
+}
+
+/// Split `s` on commas that are outside any parentheses, i.e. its top-level
+/// comma-separated groups (each still wrapped in its own parens, if any).
+fn split_top_level_groups(s: &str) -> Vec<&str> {
+    let mut groups = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                groups.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    groups.push(s[start..].trim());
+    groups
+}
+
+fn strip_parens(s: &str) -> Result<&str, String> {
+    let s = s.trim();
+    s.strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(|| format!("expected parentheses around \"{s}\""))
+}
+
+fn parse_wkt_point(s: &str) -> Result<[f64; 2], String> {
+    let mut parts = s.split_whitespace();
+    let lon: f64 = parts
+        .next()
+        .ok_or("missing longitude")?
+        .parse()
+        .map_err(|_| "bad longitude in WKT point".to_string())?;
+    let lat: f64 = parts
+        .next()
+        .ok_or("missing latitude")?
+        .parse()
+        .map_err(|_| "bad latitude in WKT point".to_string())?;
+    Ok([lon, lat])
+}
+
+fn parse_wkt_ring(s: &str) -> Result<Vec<[f64; 2]>, String> {
+    strip_parens(s)?.split(',').map(|p| parse_wkt_point(p.trim())).collect()
+}
+
+fn parse_wkt_rings(s: &str) -> Result<Vec<Vec<[f64; 2]>>, String> {
+    split_top_level_groups(s).into_iter().map(parse_wkt_ring).collect()
+}
+
+/// Parse a WKT `POLYGON` or `MULTIPOLYGON` string into the same
+/// polygon/ring/point representation used everywhere else in this module.
+pub fn parse_wkt(wkt: &str) -> Result<Vec<Vec<Vec<[f64; 2]>>>, String> {
+    let wkt = wkt.trim();
+    let open = wkt.find('(').ok_or("expected '(' after the WKT geometry type")?;
+    let tag = wkt[..open].trim().to_uppercase();
+    let body = strip_parens(&wkt[open..])?;
+
+    match tag.as_str() {
+        "POLYGON" => Ok(vec![parse_wkt_rings(body)?]),
+        "MULTIPOLYGON" => split_top_level_groups(body)
+            .into_iter()
+            .map(|poly| parse_wkt_rings(strip_parens(poly)?))
+            .collect(),
+        other => Err(format!("unsupported WKT geometry type: {other}")),
+    }
+}
+
+fn ring_to_wkt(ring: &[[f64; 2]]) -> String {
+    let points = ring.iter().map(|p| format!("{} {}", p[0], p[1])).collect::<Vec<_>>().join(", ");
+    format!("({points})")
+}
+
+fn polygon_to_wkt(poly: &[Vec<[f64; 2]>]) -> String {
+    let rings = poly.iter().map(|r| ring_to_wkt(r)).collect::<Vec<_>>().join(", ");
+    format!("({rings})")
+}
+
+/// Serialize a set of polygons (rings with holes) as a WKT `MULTIPOLYGON`.
+pub fn polygons_to_wkt(polygons: &[Vec<Vec<[f64; 2]>>]) -> String {
+    let polys = polygons.iter().map(|p| polygon_to_wkt(p)).collect::<Vec<_>>().join(", ");
+    format!("MULTIPOLYGON ({polys})")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_label_point_stays_inside_a_concave_ring() {
+        // A "C"-shaped ring whose vertex-average centroid (~5.33, ~4.44) falls in the
+        // notch carved out of its right side (x 4..10, y 4..6) — outside the
+        // ring. A bbox-center/centroid scan would land there; polylabel must
+        // find an interior point instead.
+        let ring = vec![
+            [0.0, 0.0],
+            [10.0, 0.0],
+            [10.0, 4.0],
+            [4.0, 4.0],
+            [4.0, 6.0],
+            [10.0, 6.0],
+            [10.0, 10.0],
+            [0.0, 10.0],
+            [0.0, 0.0],
+        ];
+        let (lon, lat) = ring_label_point(&ring);
+        assert!(point_in_ring(lon, lat, &ring));
+    }
+
+    /// Load a single synthetic country from a bare GeoJSON geometry literal.
+    fn load_one(geometry_json: &str) -> Country {
+        let geojson = format!(
+            r#"{{"features":[{{"properties":{{"ISO_A2_EH":"XX","NAME":"Test"}},"geometry":{geometry_json}}}]}}"#
+        );
+        let mut index = load_countries(&geojson);
+        index.countries.remove(0)
+    }
+
+    #[test]
+    fn ordinary_multipolygon_country_does_not_span_seam() {
+        // Two ordinary offshore islands, both nowhere near ±180° — this is
+        // the shape of most real multi-polygon countries (Indonesia, Japan,
+        // the UK, ...) and must not be mistaken for an antimeridian crossing.
+        let country = load_one(
+            r#"{"type":"MultiPolygon","coordinates":[
+                [[[100.0,0.0],[105.0,0.0],[105.0,5.0],[100.0,5.0],[100.0,0.0]]],
+                [[[110.0,0.0],[115.0,0.0],[115.0,5.0],[110.0,5.0],[110.0,0.0]]]
+            ]}"#,
+        );
+        assert_eq!(country.bboxes.len(), 2);
+        assert!(country.seam_bboxes.is_none());
+    }
+
+    #[test]
+    fn antimeridian_crossing_country_splits_into_sane_bboxes() {
+        let country = load_one(
+            r#"{"type":"Polygon","coordinates":[
+                [[170.0,0.0],[-170.0,0.0],[-170.0,5.0],[170.0,5.0],[170.0,0.0]]
+            ]}"#,
+        );
+        assert_eq!(country.bboxes.len(), 2);
+        let (west, east) = country.seam_bboxes.expect("ring crossed the seam");
+        assert!(west.2 - west.0 < 180.0);
+        assert!(east.2 - east.0 < 180.0);
+    }
+
+    #[test]
+    fn seam_halves_are_found_even_with_other_islands_around_them() {
+        // An exclave before the seam-crossing polygon and an offshore island
+        // after it — `seam_bboxes` must still point at the actual split
+        // halves, not at position 0 / `len() - 1` of the full bbox list.
+        let country = load_one(
+            r#"{"type":"MultiPolygon","coordinates":[
+                [[[10.0,50.0],[12.0,50.0],[12.0,52.0],[10.0,52.0],[10.0,50.0]]],
+                [[[170.0,60.0],[-170.0,60.0],[-170.0,65.0],[170.0,65.0],[170.0,60.0]]],
+                [[[150.0,40.0],[155.0,40.0],[155.0,45.0],[150.0,45.0],[150.0,40.0]]]
+            ]}"#,
+        );
+        assert_eq!(country.bboxes.len(), 4);
+        let (west, east) = country.seam_bboxes.expect("ring crossed the seam");
+        assert!(west.1 >= 60.0 && west.3 <= 65.0);
+        assert!(east.1 >= 60.0 && east.3 <= 65.0);
+    }
+
+    /// Load several synthetic countries, one per (iso_a2, geometry) pair.
+    fn load_many(countries: &[(&str, &str)]) -> CountryIndex {
+        let features: Vec<String> = countries
+            .iter()
+            .map(|(code, geometry_json)| {
+                format!(r#"{{"properties":{{"ISO_A2_EH":"{code}","NAME":"Test"}},"geometry":{geometry_json}}}"#)
+            })
+            .collect();
+        let geojson = format!(r#"{{"features":[{}]}}"#, features.join(","));
+        load_countries(&geojson)
+    }
+
+    #[test]
+    fn haversine_km_matches_known_distances() {
+        assert_eq!(haversine_km(0.0, 0.0, 0.0, 0.0), 0.0);
+        // One degree of latitude is ~111 km everywhere.
+        assert!((haversine_km(0.0, 0.0, 0.0, 1.0) - 111.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn radius_query_finds_high_latitude_country_offset_mostly_in_longitude() {
+        // 80° of longitude away but, at 80°N, compressed to well under the
+        // query radius in real km — a regression check for the 1/cos(lat)
+        // fix in the R-tree prefilter.
+        let index = load_many(&[(
+            "HI",
+            r#"{"type":"Polygon","coordinates":[[[80.0,80.0],[85.0,80.0],[85.0,82.0],[80.0,82.0],[80.0,80.0]]]}"#,
+        )]);
+        let hits = countries_within_radius(0.0, 81.0, 1600.0, &index);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, 0);
+    }
+
+    #[test]
+    fn bbox_query_rejects_rect_inside_bbox_but_outside_real_territory() {
+        // An L-shaped country: its bbox covers the notch at top-right, but no
+        // actual land sits there.
+        let index = load_many(&[(
+            "LL",
+            r#"{"type":"Polygon","coordinates":[[[0.0,0.0],[10.0,0.0],[10.0,4.0],[4.0,4.0],[4.0,10.0],[0.0,10.0],[0.0,0.0]]]}"#,
+        )]);
+
+        // Inside the bbox, inside the notch: must be rejected.
+        assert!(countries_within_bbox(5.0, 5.0, 9.0, 9.0, &index.countries).is_empty());
+
+        // Over the actual land: must be found.
+        assert_eq!(countries_within_bbox(1.0, 1.0, 3.0, 3.0, &index.countries), vec![0]);
+    }
+
+    #[test]
+    fn parse_wkt_simple_polygon() {
+        let polygons = parse_wkt("POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0))").unwrap();
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].len(), 1);
+        assert_eq!(polygons[0][0][0], [0.0, 0.0]);
+        assert_eq!(polygons[0][0].len(), 5);
+    }
+
+    #[test]
+    fn parse_wkt_polygon_with_hole() {
+        let polygons = parse_wkt("POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0), (2 2, 2 4, 4 4, 4 2, 2 2))").unwrap();
+        assert_eq!(polygons[0].len(), 2);
+        assert_eq!(polygons[0][1][0], [2.0, 2.0]);
+    }
+
+    #[test]
+    fn parse_wkt_multipolygon() {
+        let polygons =
+            parse_wkt("MULTIPOLYGON (((0 0, 1 0, 1 1, 0 1, 0 0)), ((5 5, 6 5, 6 6, 5 6, 5 5)))").unwrap();
+        assert_eq!(polygons.len(), 2);
+    }
+
+    #[test]
+    fn parse_wkt_rejects_malformed_input() {
+        assert!(parse_wkt("NOT A GEOMETRY").is_err());
+        assert!(parse_wkt("POLYGON (0 0, 1 0)").is_err());
+        assert!(parse_wkt("POLYGON ((0 0, notanumber 1))").is_err());
+    }
+
+    #[test]
+    fn polygons_to_wkt_round_trips_through_parse_wkt() {
+        let original =
+            vec![vec![vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]]]];
+        let wkt = polygons_to_wkt(&original);
+        let parsed = parse_wkt(&wkt).unwrap();
+        assert_eq!(parsed, original);
+    }
 }
@@ -2,6 +2,8 @@ mod geo;
 #[cfg(feature = "geoip")]
 mod geoip;
 mod render;
+#[cfg(feature = "tzoffset")]
+mod tzoffset;
 
 use clap::Parser;
 
@@ -27,18 +29,56 @@ struct Args {
     /// Longitude (requires --lat too)
     #[arg(long, requires = "lat", allow_hyphen_values = true)]
     lon: Option<f64>,
+
+    /// List countries within this radius in km of --lat/--lon, nearest first (query mode, no map)
+    #[arg(long)]
+    radius: Option<f64>,
+
+    /// List countries intersecting "min_lat,min_lon,max_lat,max_lon" (query mode, no map)
+    #[arg(long)]
+    bbox: Option<String>,
+
+    /// Custom region as WKT POLYGON/MULTIPOLYGON, rendered as a highlighted overlay
+    #[arg(long)]
+    wkt: Option<String>,
+
+    /// Print the resolved country's boundary as WKT MULTIPOLYGON instead of rendering a map
+    #[arg(long)]
+    export_wkt: bool,
 }
 
 static GEOJSON: &str = include_str!("../data/countries.geojson");
+static TZ_GEOJSON: &str = include_str!("../data/timezones.geojson");
 
 fn main() {
     let args = Args::parse();
-    let countries = geo::load_countries(GEOJSON);
+    let index = geo::load_countries(GEOJSON);
+
+    if let Some(radius_km) = args.radius {
+        let (lat, lon) = match (args.lat, args.lon) {
+            (Some(lat), Some(lon)) => (lat, lon),
+            _ => {
+                eprintln!("--radius requires --lat and --lon");
+                std::process::exit(1);
+            }
+        };
+        run_radius_query(&index, lat, lon, radius_km);
+        return;
+    }
+    if let Some(bbox) = &args.bbox {
+        run_bbox_query(&index, bbox);
+        return;
+    }
+    if let Some(wkt) = &args.wkt {
+        run_wkt_query(&index, wkt, args.width, args.height);
+        return;
+    }
 
     let (country_name, country_code, lat, lon) = if let Some(code) = &args.country {
         // Direct country code — find it in the data
         let code_upper = code.to_uppercase();
-        let c = countries
+        let c = index
+            .countries
             .iter()
             .find(|c| c.iso_a2 == code_upper)
             .unwrap_or_else(|| {
@@ -49,14 +89,13 @@ fn main() {
         (c.name.clone(), code_upper, label_lat, label_lon)
     } else if let (Some(lat), Some(lon)) = (args.lat, args.lon) {
         // Coordinates provided — find which country contains the point
-        let idx = geo::find_country(lon, lat, &countries)
-            .unwrap_or_else(|| {
-                eprintln!("No country found at {lat}, {lon} (ocean?)");
-                std::process::exit(1);
-            });
+        let idx = geo::find_country(lon, lat, &index).unwrap_or_else(|| {
+            eprintln!("No country found at {lat}, {lon} (ocean?)");
+            std::process::exit(1);
+        });
         (
-            countries[idx].name.clone(),
-            countries[idx].iso_a2.clone(),
+            index.countries[idx].name.clone(),
+            index.countries[idx].iso_a2.clone(),
             lat,
             lon,
         )
@@ -83,7 +122,17 @@ fn main() {
         }
     };
 
-    let map = render::render_map(&countries, &country_code, args.width, args.height);
+    if args.export_wkt {
+        let country = index
+            .countries
+            .iter()
+            .find(|c| c.iso_a2 == country_code)
+            .expect("resolved country code not found in the loaded countries");
+        println!("{}", geo::polygons_to_wkt(&country.polygons));
+        return;
+    }
+
+    let map = render::render_map(&index.countries, Some(&country_code), args.width, args.height, None);
 
     println!("You appear to be in: {country_name} ({country_code})");
     println!();
@@ -96,4 +145,118 @@ fn main() {
         lon.abs(),
         if lon >= 0.0 { "E" } else { "W" },
     );
+    print_timezone(lon, lat);
+}
+
+/// Resolve and print the IANA timezone for (lon, lat), mirroring the country
+/// lookup above. Works for all three input paths (--country, --lat/--lon, IP
+/// lookup) since they all funnel into the same (lat, lon) by this point.
+fn print_timezone(lon: f64, lat: f64) {
+    let tz_index = geo::load_timezones(TZ_GEOJSON);
+    let Some(tz_idx) = geo::find_timezone(lon, lat, &tz_index) else {
+        return;
+    };
+    let tz_name = &tz_index.zones[tz_idx].name;
+
+    #[cfg(feature = "tzoffset")]
+    match tzoffset::local_time(tz_name) {
+        Some((offset, local)) => println!("Timezone: {tz_name} (UTC{offset}, local {local})"),
+        None => println!("Timezone: {tz_name}"),
+    }
+    #[cfg(not(feature = "tzoffset"))]
+    println!("Timezone: {tz_name}");
+}
+
+fn validate_lat_lon(lat: f64, lon: f64) {
+    if !(-90.0..=90.0).contains(&lat) {
+        eprintln!("bad latitude (must be -90..90)");
+        std::process::exit(1);
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        eprintln!("bad longitude (must be -180..180)");
+        std::process::exit(1);
+    }
+}
+
+/// `--radius` query mode: list every country within `radius_km` of (lat, lon).
+fn run_radius_query(index: &geo::CountryIndex, lat: f64, lon: f64, radius_km: f64) {
+    validate_lat_lon(lat, lon);
+
+    let hits = geo::countries_within_radius(lon, lat, radius_km, index);
+    if hits.is_empty() {
+        println!("No countries found within {radius_km:.1} km of {lat:.2}, {lon:.2}");
+        return;
+    }
+    println!("Countries within {radius_km:.1} km of {lat:.2}, {lon:.2}:");
+    for (idx, dist) in hits {
+        let c = &index.countries[idx];
+        println!("  {dist:>7.1} km  {} ({})", c.name, c.iso_a2);
+    }
+}
+
+/// `--wkt` mode: render a custom WKT region as a highlighted overlay on the
+/// normal country map, and report which country contains its centroid.
+fn run_wkt_query(index: &geo::CountryIndex, wkt: &str, width: usize, height: usize) {
+    let polygons = geo::parse_wkt(wkt).unwrap_or_else(|e| {
+        eprintln!("bad --wkt: {e}");
+        std::process::exit(1);
+    });
+    let (polygons, _) = geo::split_polygons_at_antimeridian(polygons);
+    let bboxes: Vec<_> = polygons.iter().map(|p| geo::polygon_bbox(p)).collect();
+
+    let (centroid_lon, centroid_lat) = geo::ring_centroid(&polygons[0][0]);
+    let containing = geo::find_country(centroid_lon, centroid_lat, index);
+
+    let label = "WKT";
+    let extra = render::ExtraRegion {
+        polygons: &polygons,
+        bboxes: &bboxes,
+        label,
+    };
+    let map = render::render_map(&index.countries, None, width, height, Some(&extra));
+
+    match containing {
+        Some(idx) => {
+            let c = &index.countries[idx];
+            println!("Custom region centroid is in: {} ({})", c.name, c.iso_a2);
+        }
+        None => println!("Custom region centroid is not in any country (ocean?)"),
+    }
+    println!();
+    println!("{map}");
+}
+
+/// `--bbox` query mode: list every country intersecting "min_lat,min_lon,max_lat,max_lon".
+fn run_bbox_query(index: &geo::CountryIndex, bbox: &str) {
+    let parts: Vec<f64> = bbox
+        .split(',')
+        .map(|s| s.trim().parse())
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|_| {
+            eprintln!("bad --bbox (expected \"min_lat,min_lon,max_lat,max_lon\")");
+            std::process::exit(1);
+        });
+    if parts.len() != 4 {
+        eprintln!("bad --bbox (expected \"min_lat,min_lon,max_lat,max_lon\")");
+        std::process::exit(1);
+    }
+    let (min_lat, min_lon, max_lat, max_lon) = (parts[0], parts[1], parts[2], parts[3]);
+
+    validate_lat_lon(min_lat, min_lon);
+    validate_lat_lon(max_lat, max_lon);
+    if max_lat < min_lat {
+        eprintln!("bbox top latitude is below bottom latitude");
+        std::process::exit(1);
+    }
+
+    let hits = geo::countries_within_bbox(min_lat, min_lon, max_lat, max_lon, &index.countries);
+    if hits.is_empty() {
+        println!("No countries found in that bounding box");
+        return;
+    }
+    println!("Countries in {min_lat:.2},{min_lon:.2} .. {max_lat:.2},{max_lon:.2}:");
+    for idx in hits {
+        let c = &index.countries[idx];
+        println!("  {} ({})", c.name, c.iso_a2);
+    }
 }
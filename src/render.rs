@@ -1,19 +1,55 @@
 use crate::geo::Country;
 
-/// Render a zoomed-in ASCII map centered on the target country, showing borders and labels.
+/// A polygon set that isn't one of the loaded `countries` — e.g. a custom
+/// region parsed from `--wkt` — rendered as its own highlighted overlay.
+pub struct ExtraRegion<'a> {
+    pub polygons: &'a [Vec<Vec<[f64; 2]>>],
+    pub bboxes: &'a [(f64, f64, f64, f64)],
+    pub label: &'a str,
+}
+
+/// Render a zoomed-in ASCII map, showing borders and labels. Centered on the
+/// target country's bbox, or on `extra`'s bbox when an overlay region is
+/// given — in which case `extra` becomes the highlighted `#` target instead
+/// of `target_code`.
 pub fn render_map(
     countries: &[Country],
-    target_code: &str,
+    target_code: Option<&str>,
     width: usize,
     height: usize,
+    extra: Option<&ExtraRegion>,
 ) -> String {
-    // Find the target country and compute its bbox
-    let target_idx = countries
-        .iter()
-        .position(|c| c.iso_a2 == target_code)
-        .expect("Target country not found in GeoJSON");
+    let target_idx = target_code.map(|code| {
+        countries
+            .iter()
+            .position(|c| c.iso_a2 == code)
+            .expect("Target country not found in GeoJSON")
+    });
 
-    let (min_lon, min_lat, max_lon, max_lat) = countries[target_idx].bbox;
+    // A target that actually crossed the antimeridian during loading (Russia,
+    // Fiji, ...) has its west half (near -180°) unrolled past +180° so the two
+    // halves combine into one continuous span instead of a bbox union that
+    // (wrongly) spans the whole globe. This must check `seam_bboxes`
+    // specifically, not `bboxes[0]`/`bboxes[last]` — a country with other
+    // islands (Kaliningrad, Hawaii, Puerto Rico, ...) would otherwise compute
+    // its span from an unrelated island's bbox instead of the split halves.
+    // Seam handling isn't applied to `extra` regions, assumed not to cross it.
+    let seam_bboxes = if extra.is_none() { target_idx.and_then(|i| countries[i].seam_bboxes) } else { None };
+    let spans_seam = seam_bboxes.is_some();
+    let (min_lon, min_lat, max_lon, max_lat) = if let Some(extra) = extra {
+        extra.bboxes.iter().fold((f64::MAX, f64::MAX, f64::MIN, f64::MIN), |(a, b, c, d), &(lo, la, hi, ha)| {
+            (a.min(lo), b.min(la), c.max(hi), d.max(ha))
+        })
+    } else {
+        let target = &countries[target_idx.expect("render_map needs a target country or an extra region")];
+        if let Some((west, east)) = seam_bboxes {
+            (east.0, west.1.min(east.1), west.2 + 360.0, west.3.max(east.3))
+        } else {
+            target.bboxes.iter().fold((f64::MAX, f64::MAX, f64::MIN, f64::MIN), |(a, b, c, d), &(lo, la, hi, ha)| {
+                (a.min(lo), b.min(la), c.max(hi), d.max(ha))
+            })
+        }
+    };
 
     // Add generous padding so the surrounding continent is visible
     let lon_span = (max_lon - min_lon).max(4.0);
@@ -21,8 +57,11 @@ pub fn render_map(
     let pad_lon = lon_span * 1.0;
     let pad_lat = lat_span * 1.0;
 
-    let view_min_lon = (min_lon - pad_lon).max(-180.0);
-    let view_max_lon = (max_lon + pad_lon).min(180.0);
+    // A seam-spanning view is allowed to run past +180° (points far west of it
+    // are shifted +360° into the same frame by `unwrap_lon` below); otherwise
+    // clamp as before.
+    let view_min_lon = if spans_seam { min_lon - pad_lon } else { (min_lon - pad_lon).max(-180.0) };
+    let view_max_lon = if spans_seam { max_lon + pad_lon } else { (max_lon + pad_lon).min(180.0) };
     let view_min_lat = (min_lat - pad_lat).max(-90.0);
     let view_max_lat = (max_lat + pad_lat).min(90.0);
 
@@ -55,20 +94,54 @@ pub fn render_map(
     // Grid of characters
     let mut grid = vec![vec![' '; width]; height];
 
+    // When an extra region is given, it's the highlighted target instead of
+    // any country — every country renders as plain neighbor geometry.
+    let is_highlighted_country = |i: usize| extra.is_none() && Some(i) == target_idx;
+
     // Rasterize polygon edges onto the grid
     for (i, country) in countries.iter().enumerate() {
-        let (c_min_lon, c_min_lat, c_max_lon, c_max_lat) = country.bbox;
-        if c_max_lon < vp_min_lon
-            || c_min_lon > vp_min_lon + final_lon_range
-            || c_max_lat < vp_max_lat - final_lat_range
-            || c_min_lat > vp_max_lat
-        {
-            continue;
-        }
+        for (poly, &(c_min_lon, c_min_lat, c_max_lon, c_max_lat)) in country.polygons.iter().zip(&country.bboxes) {
+            let c_min_lon = unwrap_lon(c_min_lon, vp_min_lon, spans_seam);
+            let c_max_lon = unwrap_lon(c_max_lon, vp_min_lon, spans_seam);
+            if c_max_lon < vp_min_lon
+                || c_min_lon > vp_min_lon + final_lon_range
+                || c_max_lat < vp_max_lat - final_lat_range
+                || c_min_lat > vp_max_lat
+            {
+                continue;
+            }
+
+            let border_ch = if is_highlighted_country(i) { '#' } else { '\u{00b7}' };
 
-        let border_ch = if i == target_idx { '#' } else { '\u{00b7}' };
+            for ring in poly {
+                if ring.len() < 2 {
+                    continue;
+                }
+                for edge in ring.windows(2) {
+                    rasterize_edge(
+                        unwrap_lon(edge[0][0], vp_min_lon, spans_seam),
+                        edge[0][1],
+                        unwrap_lon(edge[1][0], vp_min_lon, spans_seam),
+                        edge[1][1],
+                        vp_min_lon,
+                        vp_max_lat,
+                        lon_per_col,
+                        lat_per_row,
+                        width,
+                        height,
+                        border_ch,
+                        is_highlighted_country(i),
+                        &mut grid,
+                    );
+                }
+            }
+        }
+    }
 
-        for poly in &country.polygons {
+    // An extra region (e.g. from --wkt) overlays on top of every country as
+    // the highlighted target, with its own border and label.
+    if let Some(extra) = extra {
+        for poly in extra.polygons {
             for ring in poly {
                 if ring.len() < 2 {
                     continue;
@@ -85,8 +158,8 @@ pub fn render_map(
                         lat_per_row,
                         width,
                         height,
-                        border_ch,
-                        i == target_idx,
+                        '#',
+                        true,
                         &mut grid,
                     );
                 }
@@ -101,6 +174,7 @@ pub fn render_map(
         }
 
         let (label_lon, label_lat) = country.label_pos;
+        let label_lon = unwrap_lon(label_lon, vp_min_lon, spans_seam);
 
         let col = ((label_lon - vp_min_lon) / lon_per_col) as isize;
         let row = ((vp_max_lat - label_lat) / lat_per_row) as isize;
@@ -115,13 +189,31 @@ pub fn render_map(
                 let c = c as usize;
                 let existing = grid[r][c];
                 // Target label always writes; neighbor labels only on empty or neighbor border
-                if i == target_idx || existing == ' ' || existing == '\u{00b7}' {
+                if is_highlighted_country(i) || existing == ' ' || existing == '\u{00b7}' {
                     grid[r][c] = ch;
                 }
             }
         }
     }
 
+    // Place the extra region's own label at its bbox center
+    if let Some(extra) = extra {
+        let (lon0, lat0, lon1, lat1) = extra.bboxes.iter().fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(a, b, c, d), &(lo, la, hi, ha)| (a.min(lo), b.min(la), c.max(hi), d.max(ha)),
+        );
+        let col = (((lon0 + lon1) / 2.0 - vp_min_lon) / lon_per_col) as isize;
+        let row = ((vp_max_lat - (lat0 + lat1) / 2.0) / lat_per_row) as isize;
+        let start_col = col - (extra.label.len() as isize / 2);
+
+        for (j, ch) in extra.label.chars().enumerate() {
+            let c = start_col + j as isize;
+            if c >= 0 && (c as usize) < width && row >= 0 && (row as usize) < height {
+                grid[row as usize][c as usize] = ch;
+            }
+        }
+    }
+
     // Render grid to string
     grid.iter()
         .map(|row| row.iter().collect::<String>())
@@ -129,6 +221,19 @@ pub fn render_map(
         .join("\n")
 }
 
+/// When the viewport spans the ±180° antimeridian, shift a longitude by +360°
+/// if it lies more than 180° west of the viewport's left edge, so it lands in
+/// the same continuous frame
+/// as the rest of the drawn geometry, instead of wrapping around and tearing
+/// the map in half.
+fn unwrap_lon(lon: f64, vp_min_lon: f64, spans_seam: bool) -> f64 {
+    if spans_seam && lon < vp_min_lon - 180.0 {
+        lon + 360.0
+    } else {
+        lon
+    }
+}
+
 /// Rasterize a line segment onto the grid using Bresenham's algorithm.
 fn rasterize_edge(
     lon0: f64,
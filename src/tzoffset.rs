@@ -0,0 +1,13 @@
+use chrono::{Offset, Utc};
+use chrono_tz::Tz;
+
+/// Current UTC offset (e.g. "+02:00") and local time (e.g. "14:37") for an
+/// IANA timezone name. Returns `None` if the name isn't recognized.
+///
+/// `Tz`'s `Offset` impl formats as the zone's abbreviation (e.g. "CEST"), not
+/// a numeric offset, so `.fix()` is needed to get the fixed UTC offset first.
+pub fn local_time(tz_name: &str) -> Option<(String, String)> {
+    let tz: Tz = tz_name.parse().ok()?;
+    let now = Utc::now().with_timezone(&tz);
+    Some((now.offset().fix().to_string(), now.format("%H:%M").to_string()))
+}